@@ -5,6 +5,25 @@ pub mod models {
         fn phi(&self, u: Complex64, t: f64, s0: f64, r: f64, q: f64) -> Complex64;
     }
 
+    /// Cumulants of ln S_T, used to pick a truncation range for the COS method.
+    /// Models without a convenient closed-form fourth cumulant may leave `c4` at
+    /// its default of zero; the range is then driven by `c2` alone.
+    pub trait Cumulants {
+        fn c1(&self, t: f64, s0: f64, r: f64, q: f64) -> f64;
+        fn c2(&self, t: f64) -> f64;
+        fn c4(&self, _t: f64) -> f64 {
+            0.0
+        }
+    }
+
+    /// Bumps a model's volatility parameter, for the vega finite-difference
+    /// fallback: each model calls a different field by a different name, so
+    /// this is the one piece of per-model knowledge that can't be read off
+    /// `phi` alone.
+    pub trait VolBump: Sized {
+        fn bump_vol(&self, h: f64) -> Self;
+    }
+
     #[derive(Clone, Copy, Debug)]
     pub struct BlackScholes {
         pub sigma: f64,
@@ -19,6 +38,22 @@ pub mod models {
         }
     }
 
+    impl Cumulants for BlackScholes {
+        fn c1(&self, t: f64, s0: f64, r: f64, q: f64) -> f64 {
+            s0.ln() + (r - q - 0.5 * self.sigma * self.sigma) * t
+        }
+
+        fn c2(&self, t: f64) -> f64 {
+            self.sigma * self.sigma * t
+        }
+    }
+
+    impl VolBump for BlackScholes {
+        fn bump_vol(&self, h: f64) -> Self {
+            BlackScholes { sigma: self.sigma + h }
+        }
+    }
+
     #[derive(Clone, Copy, Debug)]
     pub struct Heston {
         pub kappa: f64,
@@ -52,6 +87,32 @@ pub mod models {
         }
     }
 
+    impl Cumulants for Heston {
+        fn c1(&self, t: f64, s0: f64, r: f64, q: f64) -> f64 {
+            // Mean of ln S_T under the risk-neutral drift: the drift less half the
+            // expected integrated CIR variance, which is what `c2` returns.
+            s0.ln() + (r - q) * t - 0.5 * self.c2(t)
+        }
+
+        fn c2(&self, t: f64) -> f64 {
+            let avg_var = if self.kappa * t > 1e-8 {
+                self.theta + (self.v0 - self.theta) * (1.0 - (-self.kappa * t).exp()) / (self.kappa * t)
+            } else {
+                self.v0
+            };
+            avg_var * t
+        }
+    }
+
+    impl VolBump for Heston {
+        fn bump_vol(&self, h: f64) -> Self {
+            // Vega is conventionally the price sensitivity to the current
+            // variance level, not to vol-of-vol (that sensitivity is volga/
+            // vomma, a different Greek the caller doesn't get from `Greeks`).
+            Heston { v0: self.v0 + h, ..*self }
+        }
+    }
+
     #[derive(Clone, Copy, Debug)]
     pub struct VarianceGamma {
         pub sigma: f64,
@@ -74,6 +135,32 @@ pub mod models {
             (iu * drift).exp() * base.powf(-t / nu)
         }
     }
+
+    impl Cumulants for VarianceGamma {
+        fn c1(&self, t: f64, s0: f64, r: f64, q: f64) -> f64 {
+            let sigma2 = self.sigma * self.sigma;
+            let omega = -((1.0 - self.theta * self.nu - 0.5 * sigma2 * self.nu).ln()) / self.nu;
+            s0.ln() + (r - q + omega) * t + self.theta * t
+        }
+
+        fn c2(&self, t: f64) -> f64 {
+            (self.sigma * self.sigma + self.theta * self.theta * self.nu) * t
+        }
+
+        fn c4(&self, t: f64) -> f64 {
+            let sigma2 = self.sigma * self.sigma;
+            let theta2 = self.theta * self.theta;
+            3.0 * self.nu
+                * (sigma2 * sigma2 + 2.0 * theta2 * theta2 * self.nu * self.nu + 4.0 * sigma2 * theta2 * self.nu)
+                * t
+        }
+    }
+
+    impl VolBump for VarianceGamma {
+        fn bump_vol(&self, h: f64) -> Self {
+            VarianceGamma { sigma: self.sigma + h, ..*self }
+        }
+    }
 }
 
 pub mod fft_pricer {
@@ -81,8 +168,9 @@ pub mod fft_pricer {
     use rustfft::FftPlanner;
     use std::f64::consts::PI;
 
-    use crate::models::CharacteristicFunction;
+    use crate::models::{CharacteristicFunction, Cumulants, VolBump};
 
+    #[derive(Clone, Copy, Debug)]
     pub struct CarrMadanParams {
         pub alpha: f64,
         pub eta: f64,
@@ -110,9 +198,217 @@ pub mod fft_pricer {
 
         let lambda = 2.0 * PI / ((n_points as f64) * eta);
 
-        let mut y: Vec<Complex64> = Vec::with_capacity(n_points);
+        let y = carr_madan_integrand(model, s0, r, q, t, Damping { alpha, eta, beta }, n_points);
+
+        let mut planner = FftPlanner::<f64>::new();
+        let fft = planner.plan_fft_forward(n_points);
+        let mut y_fft = y;
+        fft.process(&mut y_fft);
+
+        let mut k_grid: Vec<f64> = Vec::with_capacity(n_points);
+        let mut call_prices: Vec<f64> = Vec::with_capacity(n_points);
+        for (m, y_m) in y_fft.iter().enumerate() {
+            let k_m = -beta + (m as f64) * lambda;
+            let c_m = (-(alpha * k_m)).exp() * y_m.re / PI;
+            k_grid.push(k_m);
+            call_prices.push(c_m);
+        }
+
+        GridResult { k: k_grid, call_prices }
+    }
+
+    /// Index of the log-strike grid node closest to `target_k`.
+    fn nearest_index(k_grid: &[f64], target_k: f64) -> usize {
+        let mut best_idx = 0usize;
+        let mut best_err = f64::INFINITY;
+        for (idx, &k) in k_grid.iter().enumerate() {
+            let err = (k - target_k).abs();
+            if err < best_err {
+                best_err = err;
+                best_idx = idx;
+            }
+        }
+        best_idx
+    }
+
+    /// Nearest-node call price lookup, exposed so callers outside this module
+    /// (e.g. calibration objectives) can reuse the same grid for many strikes.
+    pub fn nearest_call_price(grid: &GridResult, k_strike: f64) -> f64 {
+        let idx = nearest_index(&grid.k, k_strike.ln());
+        grid.call_prices[idx]
+    }
+
+    /// Nearest-node call -> put conversion shared by every grid engine: looks up
+    /// the grid's closest log-strike to `k_strike` and applies put-call parity.
+    fn put_from_grid(grid: &GridResult, s0: f64, r: f64, q: f64, t: f64, k_strike: f64) -> f64 {
+        let call_price = nearest_call_price(grid, k_strike);
+        // Put from parity: P = C - S0 e^{-qT} + K e^{-rT}
+        call_price - s0 * (-q * t).exp() + k_strike * (-r * t).exp()
+    }
+
+    pub fn price_put_at_strike<M: CharacteristicFunction>(
+        model: &M,
+        s0: f64,
+        r: f64,
+        q: f64,
+        t: f64,
+        params: CarrMadanParams,
+        k_strike: f64,
+    ) -> f64 {
+        let grid = price_calls_grid(model, s0, r, q, t, params);
+        put_from_grid(&grid, s0, r, q, t, k_strike)
+    }
+
+    pub struct GreeksGridResult {
+        pub k: Vec<f64>,
+        pub call_prices: Vec<f64>,
+        pub delta: Vec<f64>, // dC/dS0
+        pub gamma: Vec<f64>, // d^2C/dS0^2
+    }
+
+    /// Delta and gamma on the same FFT grid as [`price_calls_grid`], at
+    /// essentially no extra cost: `ln S0` enters the integrand only through
+    /// `phi`'s drift term, so differentiating the integrand in `S0` just
+    /// multiplies each node by `i*u_shifted/S0` (delta) or
+    /// `i*u_shifted*(i*u_shifted - 1)/S0^2` (gamma) before the FFT.
+    pub fn price_call_greeks_grid<M: CharacteristicFunction>(
+        model: &M,
+        s0: f64,
+        r: f64,
+        q: f64,
+        t: f64,
+        params: CarrMadanParams,
+    ) -> GreeksGridResult {
+        let n_points = 1usize << params.n;
+        let eta = params.eta;
+        let alpha = params.alpha;
+        let beta = params.beta;
+        let lambda = 2.0 * PI / ((n_points as f64) * eta);
+
+        let y = carr_madan_integrand(model, s0, r, q, t, Damping { alpha, eta, beta }, n_points);
+        let i = Complex64::new(0.0, 1.0);
+
+        let mut delta_y: Vec<Complex64> = Vec::with_capacity(n_points);
+        let mut gamma_y: Vec<Complex64> = Vec::with_capacity(n_points);
+        for (j, &y_j) in y.iter().enumerate() {
+            let u_j = (j as f64) * eta;
+            let u_shifted = Complex64::new(u_j, -(alpha + 1.0));
+            let d1 = i * u_shifted / s0;
+            delta_y.push(y_j * d1);
+            gamma_y.push(y_j * (d1 * d1 - i * u_shifted / (s0 * s0)));
+        }
+
+        let mut planner = FftPlanner::<f64>::new();
+        let fft = planner.plan_fft_forward(n_points);
+        let mut call_fft = y;
+        let mut delta_fft = delta_y;
+        let mut gamma_fft = gamma_y;
+        fft.process(&mut call_fft);
+        fft.process(&mut delta_fft);
+        fft.process(&mut gamma_fft);
+
+        let mut k_grid: Vec<f64> = Vec::with_capacity(n_points);
+        let mut call_prices: Vec<f64> = Vec::with_capacity(n_points);
+        let mut delta: Vec<f64> = Vec::with_capacity(n_points);
+        let mut gamma: Vec<f64> = Vec::with_capacity(n_points);
+        for m in 0..n_points {
+            let k_m = -beta + (m as f64) * lambda;
+            let scale = (-(alpha * k_m)).exp() / PI;
+            k_grid.push(k_m);
+            call_prices.push(scale * call_fft[m].re);
+            delta.push(scale * delta_fft[m].re);
+            gamma.push(scale * gamma_fft[m].re);
+        }
+
+        GreeksGridResult { k: k_grid, call_prices, delta, gamma }
+    }
+
+    pub struct Greeks {
+        pub delta: f64,
+        pub gamma: f64,
+        pub vega: f64,
+        pub rho: f64,
+        pub theta: f64, // dV/dt (time to maturity), not the calendar-time convention
+    }
+
+    const GREEKS_BUMP: f64 = 1e-4;
+
+    /// Call Greeks at a single strike: delta/gamma read off the analytic
+    /// [`price_call_greeks_grid`], vega/rho/theta from a central finite
+    /// difference since they have no equally cheap closed form here.
+    pub fn call_greeks_at_strike<M: CharacteristicFunction + VolBump>(
+        model: &M,
+        s0: f64,
+        r: f64,
+        q: f64,
+        t: f64,
+        params: CarrMadanParams,
+        k_strike: f64,
+    ) -> Greeks {
+        let grid = price_call_greeks_grid(model, s0, r, q, t, params);
+        let idx = nearest_index(&grid.k, k_strike.ln());
+        let delta = grid.delta[idx];
+        let gamma = grid.gamma[idx];
+
+        let price_at = |m: &M, r: f64, t: f64| {
+            nearest_call_price(&price_calls_grid(m, s0, r, q, t, params), k_strike)
+        };
+
+        let vega = (price_at(&model.bump_vol(GREEKS_BUMP), r, t) - price_at(&model.bump_vol(-GREEKS_BUMP), r, t))
+            / (2.0 * GREEKS_BUMP);
+        let rho = (price_at(model, r + GREEKS_BUMP, t) - price_at(model, r - GREEKS_BUMP, t)) / (2.0 * GREEKS_BUMP);
+        let theta = (price_at(model, r, t + GREEKS_BUMP) - price_at(model, r, t - GREEKS_BUMP)) / (2.0 * GREEKS_BUMP);
+
+        Greeks { delta, gamma, vega, rho, theta }
+    }
+
+    /// Put Greeks at a single strike, derived from [`call_greeks_at_strike`]
+    /// via put-call parity (`P = C - S0 e^{-qT} + K e^{-rT}`) rather than a
+    /// second finite-difference pass.
+    pub fn greeks_at_strike<M: CharacteristicFunction + VolBump>(
+        model: &M,
+        s0: f64,
+        r: f64,
+        q: f64,
+        t: f64,
+        params: CarrMadanParams,
+        k_strike: f64,
+    ) -> Greeks {
+        let call = call_greeks_at_strike(model, s0, r, q, t, params, k_strike);
+        Greeks {
+            delta: call.delta - (-q * t).exp(),
+            gamma: call.gamma,
+            vega: call.vega,
+            rho: call.rho - k_strike * t * (-r * t).exp(),
+            theta: call.theta + s0 * q * (-q * t).exp() - k_strike * r * (-r * t).exp(),
+        }
+    }
+
+    /// The three damping/shift parameters [`CarrMadanParams`] and [`FrftParams`]
+    /// share, bundled so the integrand builder doesn't need a long argument list.
+    #[derive(Clone, Copy)]
+    struct Damping {
+        alpha: f64,
+        eta: f64,
+        beta: f64,
+    }
+
+    /// Same Carr-Madan damped integrand as [`price_calls_grid`], but exposed on
+    /// its own so the fractional-FFT engine can reuse it without depending on a
+    /// fixed `lambda = 2*pi/(N*eta)`.
+    fn carr_madan_integrand<M: CharacteristicFunction>(
+        model: &M,
+        s0: f64,
+        r: f64,
+        q: f64,
+        t: f64,
+        damping: Damping,
+        n_points: usize,
+    ) -> Vec<Complex64> {
+        let Damping { alpha, eta, beta } = damping;
         let i = Complex64::new(0.0, 1.0);
         let discount = (-r * t).exp();
+        let mut y: Vec<Complex64> = Vec::with_capacity(n_points);
 
         for j in 0..n_points {
             let u_j = (j as f64) * eta;
@@ -126,20 +422,85 @@ pub mod fft_pricer {
             let weight = weight / 3.0;
 
             let factor = (i * (beta * u_j)).exp();
-            let val = psi * factor * (eta * weight);
-            y.push(val);
+            y.push(psi * factor * (eta * weight));
+        }
+        y
+    }
+
+    /// Fractional DFT `G_k = sum_j y_j * exp(-i*2*pi*gamma*j*k)` via the
+    /// Bluestein construction: chirp, convolve (two forward FFTs, one inverse),
+    /// de-chirp. Unlike the plain FFT, `gamma` need not make `y.len() * gamma`
+    /// an integer, which is what decouples the strike spacing from `eta`.
+    fn fractional_fft(y: &[Complex64], gamma: f64) -> Vec<Complex64> {
+        let n_points = y.len();
+        let m = 2 * n_points;
+        let chirp = |j: f64| {
+            let theta = PI * gamma * j * j;
+            Complex64::new(theta.cos(), -theta.sin()) // exp(-i*theta)
+        };
+
+        let mut a = vec![Complex64::new(0.0, 0.0); m];
+        let mut b = vec![Complex64::new(0.0, 0.0); m];
+        for j in 0..n_points {
+            let jf = j as f64;
+            a[j] = y[j] * chirp(jf);
+            b[j] = chirp(jf).conj();
+            if j > 0 {
+                b[m - j] = chirp(jf).conj();
+            }
         }
 
         let mut planner = FftPlanner::<f64>::new();
-        let fft = planner.plan_fft_forward(n_points);
-        let mut y_fft = y;
-        fft.process(&mut y_fft);
+        let fwd = planner.plan_fft_forward(m);
+        let inv = planner.plan_fft_inverse(m);
+
+        fwd.process(&mut a);
+        fwd.process(&mut b);
+        let mut c: Vec<Complex64> = a.iter().zip(b.iter()).map(|(x, y)| x * y).collect();
+        inv.process(&mut c);
+
+        (0..n_points)
+            .map(|k| c[k] * chirp(k as f64) / Complex64::new(m as f64, 0.0))
+            .collect()
+    }
+
+    pub struct FrftParams {
+        pub alpha: f64,
+        pub eta: f64,
+        pub n: usize,  // number of integration points is N = 2^n
+        /// Log-strike shift. Unlike [`CarrMadanParams::beta`], the grid is no
+        /// longer automatically wide enough to reach any strike you ask for:
+        /// since the grid spans `[-beta, -beta + (N-1)*lambda]`, `beta` must be
+        /// chosen so that range covers the strikes you intend to query, e.g.
+        /// `beta = -(ln(k_strike) - (N as f64 / 2.0) * lambda)` to center it.
+        pub beta: f64,
+        pub lambda: f64, // output log-strike spacing, chosen independently of eta
+    }
+
+    /// Carr-Madan pricing on a strike grid whose spacing is set by `lambda`
+    /// instead of being pinned to `2*pi/(N*eta)`, using the fractional FFT.
+    /// Lets the caller refine the strike grid without coarsening the
+    /// integration grid (or vice versa).
+    pub fn price_calls_grid_frft<M: CharacteristicFunction>(
+        model: &M,
+        s0: f64,
+        r: f64,
+        q: f64,
+        t: f64,
+        params: FrftParams,
+    ) -> GridResult {
+        let n_points = 1usize << params.n;
+        let gamma = params.eta * params.lambda / (2.0 * PI);
+
+        let damping = Damping { alpha: params.alpha, eta: params.eta, beta: params.beta };
+        let y = carr_madan_integrand(model, s0, r, q, t, damping, n_points);
+        let g = fractional_fft(&y, gamma);
 
         let mut k_grid: Vec<f64> = Vec::with_capacity(n_points);
         let mut call_prices: Vec<f64> = Vec::with_capacity(n_points);
-        for m in 0..n_points {
-            let k_m = -beta + (m as f64) * lambda;
-            let c_m = (-(alpha * k_m)).exp() * (y_fft[m].re) / PI;
+        for (m, g_m) in g.iter().enumerate() {
+            let k_m = -params.beta + (m as f64) * params.lambda;
+            let c_m = (-(params.alpha * k_m)).exp() * g_m.re / PI;
             k_grid.push(k_m);
             call_prices.push(c_m);
         }
@@ -147,29 +508,670 @@ pub mod fft_pricer {
         GridResult { k: k_grid, call_prices }
     }
 
-    pub fn price_put_at_strike<M: CharacteristicFunction>(
+    /// Put price from the fractional-FFT grid; needless nearest-node error is
+    /// bounded only by `lambda`, which the caller is now free to shrink.
+    pub fn price_put_at_strike_frft<M: CharacteristicFunction>(
         model: &M,
         s0: f64,
         r: f64,
         q: f64,
         t: f64,
-        params: CarrMadanParams,
+        params: FrftParams,
         k_strike: f64,
     ) -> f64 {
-        let grid = price_calls_grid(model, s0, r, q, t, params);
-        let target_k = k_strike.ln();
-        let mut best_idx = 0usize;
-        let mut best_err = f64::INFINITY;
-        for (idx, &k) in grid.k.iter().enumerate() {
-            let err = (k - target_k).abs();
-            if err < best_err {
-                best_err = err;
-                best_idx = idx;
+        let grid = price_calls_grid_frft(model, s0, r, q, t, params);
+        put_from_grid(&grid, s0, r, q, t, k_strike)
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct CosParams {
+        pub n: usize, // number of cosine terms
+        pub l: f64,   // truncation-range width, in standard deviations (~10)
+    }
+
+    impl Default for CosParams {
+        fn default() -> Self {
+            CosParams { n: 128, l: 10.0 }
+        }
+    }
+
+    /// Truncation range `[a, b]` for ln S_T, from the cumulants of the model
+    /// (Fang & Oosterlee's rule of thumb: `c1 +/- L*sqrt(c2 + sqrt(c4))`).
+    fn truncation_range<M: Cumulants>(model: &M, t: f64, s0: f64, r: f64, q: f64, l: f64) -> (f64, f64) {
+        let c1 = model.c1(t, s0, r, q);
+        let c2 = model.c2(t);
+        let c4 = model.c4(t);
+        let width = l * (c2 + c4.abs().sqrt()).sqrt();
+        (c1 - width, c1 + width)
+    }
+
+    /// psi_k(c, d) for the COS series, with truncation lower bound `a`.
+    fn psi_k(k: usize, c: f64, d: f64, a: f64, b: f64) -> f64 {
+        if k == 0 {
+            d - c
+        } else {
+            let omega = (k as f64) * PI / (b - a);
+            (omega * (d - a)).sin() / omega - (omega * (c - a)).sin() / omega
+        }
+    }
+
+    /// chi_k(c, d) for the COS series, with truncation lower bound `a`.
+    fn chi_k(k: usize, c: f64, d: f64, a: f64, b: f64) -> f64 {
+        let omega = (k as f64) * PI / (b - a);
+        let term_d = (omega * (d - a)).cos() * d.exp() + omega * (omega * (d - a)).sin() * d.exp();
+        let term_c = (omega * (c - a)).cos() * c.exp() + omega * (omega * (c - a)).sin() * c.exp();
+        (term_d - term_c) / (1.0 + omega * omega)
+    }
+
+    /// Put price via the Fourier-cosine (COS) expansion of Fang & Oosterlee.
+    /// Converges exponentially in `params.n` and needs neither a damping
+    /// parameter nor a strike grid, unlike [`price_put_at_strike`].
+    pub fn price_put_cos<M: CharacteristicFunction + Cumulants>(
+        model: &M,
+        s0: f64,
+        r: f64,
+        q: f64,
+        t: f64,
+        params: CosParams,
+        k_strike: f64,
+    ) -> f64 {
+        let (a, b) = truncation_range(model, t, s0, r, q, params.l);
+        let ln_k = k_strike.ln();
+        // The cumulants-implied range can be narrower than the distance from
+        // c1 to the strike (e.g. as sigma -> 0); widen it to always contain
+        // ln_k; psi_k/chi_k are only valid antiderivatives on [a, b] when the
+        // payoff's integration bound ln_k falls inside that range.
+        let a = a.min(ln_k);
+        let b = b.max(ln_k);
+        // y = ln S_T; the put payoff is in the money for y in [a, ln K].
+        let u_k = |k: usize| (2.0 / (b - a)) * (k_strike * psi_k(k, a, ln_k, a, b) - chi_k(k, a, ln_k, a, b));
+
+        let mut sum = 0.0;
+        for k in 0..params.n {
+            let omega = (k as f64) * PI / (b - a);
+            let phi_val = model.phi(Complex64::new(omega, 0.0), t, s0, r, q);
+            let angle = omega * a;
+            let re = phi_val.re * angle.cos() + phi_val.im * angle.sin();
+            let weight = if k == 0 { 0.5 } else { 1.0 };
+            sum += weight * re * u_k(k);
+        }
+
+        (-r * t).exp() * sum
+    }
+
+    /// Call price via put-call parity from [`price_put_cos`].
+    pub fn price_call_cos<M: CharacteristicFunction + Cumulants>(
+        model: &M,
+        s0: f64,
+        r: f64,
+        q: f64,
+        t: f64,
+        params: CosParams,
+        k_strike: f64,
+    ) -> f64 {
+        let put = price_put_cos(model, s0, r, q, t, CosParams { n: params.n, l: params.l }, k_strike);
+        put + s0 * (-q * t).exp() - k_strike * (-r * t).exp()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::models::BlackScholes;
+
+        // Reference closed-form Black-Scholes price, independent of this
+        // module's own machinery, to check the FFT/COS pricers against.
+        fn erf(x: f64) -> f64 {
+            // Abramowitz & Stegun 7.1.26, accurate to ~1.5e-7.
+            let sign = if x < 0.0 { -1.0 } else { 1.0 };
+            let x = x.abs();
+            let t = 1.0 / (1.0 + 0.3275911 * x);
+            let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+            sign * (1.0 - poly * (-x * x).exp())
+        }
+
+        fn norm_cdf(x: f64) -> f64 {
+            0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+        }
+
+        fn bs_put(s0: f64, k: f64, t: f64, r: f64, q: f64, sigma: f64) -> f64 {
+            let d1 = ((s0 / k).ln() + (r - q + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+            let d2 = d1 - sigma * t.sqrt();
+            k * (-r * t).exp() * norm_cdf(-d2) - s0 * (-q * t).exp() * norm_cdf(-d1)
+        }
+
+        fn bs_call(s0: f64, k: f64, t: f64, r: f64, q: f64, sigma: f64) -> f64 {
+            let d1 = ((s0 / k).ln() + (r - q + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+            let d2 = d1 - sigma * t.sqrt();
+            s0 * (-q * t).exp() * norm_cdf(d1) - k * (-r * t).exp() * norm_cdf(d2)
+        }
+
+        #[test]
+        fn price_calls_grid_frft_matches_black_scholes() {
+            let (s0, k, t, r, q, sigma): (f64, f64, f64, f64, f64, f64) = (100.0, 105.0, 0.75, 0.03, 0.01, 0.22);
+            let model = BlackScholes { sigma };
+
+            let n = 12;
+            let lambda = 0.01;
+            // Center the output grid on ln(k), per FrftParams::beta's doc.
+            let beta = -(k.ln() - (1usize << n) as f64 / 2.0 * lambda);
+            let params = FrftParams { alpha: 1.5, eta: 0.1, n, beta, lambda };
+            let grid = price_calls_grid_frft(&model, s0, r, q, t, params);
+            let idx = nearest_index(&grid.k, k.ln());
+
+            let analytic = bs_call(s0, k, t, r, q, sigma);
+            assert!(
+                (grid.call_prices[idx] - analytic).abs() < 1e-4,
+                "frft={} analytic={analytic}",
+                grid.call_prices[idx]
+            );
+        }
+
+        #[test]
+        fn price_put_cos_matches_black_scholes() {
+            let (s0, k, t, r, q, sigma) = (100.0, 95.0, 0.75, 0.03, 0.01, 0.22);
+            let model = BlackScholes { sigma };
+
+            let cos = price_put_cos(&model, s0, r, q, t, CosParams::default(), k);
+            let analytic = bs_put(s0, k, t, r, q, sigma);
+
+            assert!((cos - analytic).abs() < 1e-4, "cos={cos} analytic={analytic}");
+        }
+    }
+}
+
+pub mod calibration {
+    use std::f64::consts::PI;
+
+    use crate::fft_pricer::{nearest_call_price, price_calls_grid, CarrMadanParams};
+    use crate::models::{CharacteristicFunction, Heston, VarianceGamma};
+
+    /// A single observed option quote. Quotes are assumed to be call prices;
+    /// put quotes should be converted to calls via parity before calibrating.
+    #[derive(Clone, Copy, Debug)]
+    pub struct Quote {
+        pub strike: f64,
+        pub maturity: f64,
+        pub market_price: f64,
+        pub weight: f64,
+    }
+
+    /// Encodes/decodes a model's free parameters as a flat vector for the
+    /// optimizer, and flags parameter combinations the optimizer must avoid.
+    pub trait ModelParams: Sized {
+        fn from_vector(x: &[f64]) -> Self;
+        fn to_vector(&self) -> Vec<f64>;
+        fn is_valid(&self) -> bool;
+    }
+
+    impl ModelParams for Heston {
+        fn from_vector(x: &[f64]) -> Self {
+            Heston { kappa: x[0], theta: x[1], vol_of_vol: x[2], rho: x[3], v0: x[4] }
+        }
+
+        fn to_vector(&self) -> Vec<f64> {
+            vec![self.kappa, self.theta, self.vol_of_vol, self.rho, self.v0]
+        }
+
+        fn is_valid(&self) -> bool {
+            self.kappa > 0.0
+                && self.theta > 0.0
+                && self.vol_of_vol > 0.0
+                && self.v0 > 0.0
+                && self.rho > -1.0
+                && self.rho < 1.0
+                && 2.0 * self.kappa * self.theta > self.vol_of_vol * self.vol_of_vol // Feller condition
+        }
+    }
+
+    impl ModelParams for VarianceGamma {
+        fn from_vector(x: &[f64]) -> Self {
+            VarianceGamma { sigma: x[0], nu: x[1], theta: x[2] }
+        }
+
+        fn to_vector(&self) -> Vec<f64> {
+            vec![self.sigma, self.nu, self.theta]
+        }
+
+        fn is_valid(&self) -> bool {
+            self.sigma > 0.0
+                && self.nu > 0.0
+                && 1.0 - self.theta * self.nu - 0.5 * self.sigma * self.sigma * self.nu > 0.0
+        }
+    }
+
+    /// Groups quotes by maturity so each objective evaluation prices a whole
+    /// strike chain with a single [`price_calls_grid`] call per maturity.
+    fn group_by_maturity(quotes: &[Quote]) -> Vec<(f64, Vec<Quote>)> {
+        let mut groups: Vec<(f64, Vec<Quote>)> = Vec::new();
+        for &quote in quotes {
+            match groups.iter_mut().find(|(t, _)| (*t - quote.maturity).abs() < 1e-9) {
+                Some(group) => group.1.push(quote),
+                None => groups.push((quote.maturity, vec![quote])),
             }
         }
-        let call_price = grid.call_prices[best_idx];
-        // Put from parity: P = C - S0 e^{-qT} + K e^{-rT}
-        let put_price = call_price - s0 * (-q * t).exp() + k_strike * (-r * t).exp();
-        put_price
+        groups
+    }
+
+    /// Weighted sum of squared pricing errors for `model` against `groups`,
+    /// pricing each maturity's whole strike chain with one FFT grid.
+    fn pricing_error<M: CharacteristicFunction>(model: &M, s0: f64, r: f64, q: f64, groups: &[(f64, Vec<Quote>)]) -> f64 {
+        let mut sse = 0.0;
+        for (t, group) in groups {
+            let (alpha, eta, n) = (1.5, 0.25, 10);
+            let n_points = 1usize << n;
+            let lambda = 2.0 * PI / ((n_points as f64) * eta);
+            // Center the grid on this maturity's own quoted strikes rather than
+            // hardcoding beta=0 (log-strike 0, i.e. a strike of 1): quotes with
+            // strike < 1 would otherwise have no nearby grid node at all.
+            let center_ln_k = group.iter().map(|quote| quote.strike.ln()).sum::<f64>() / group.len() as f64;
+            let beta = -(center_ln_k - (n_points as f64 / 2.0) * lambda);
+            let params = CarrMadanParams { alpha, eta, n, beta };
+            let grid = price_calls_grid(model, s0, r, q, *t, params);
+            for quote in group {
+                let model_price = nearest_call_price(&grid, quote.strike);
+                let err = model_price - quote.market_price;
+                sse += quote.weight * err * err;
+            }
+        }
+        sse
+    }
+
+    /// Sentinel objective value for invalid/non-finite parameter points.
+    /// Shared between [`calibrate`]'s objective and `nelder_mead`'s
+    /// convergence check so a simplex that hasn't found a single feasible
+    /// vertex yet is never mistaken for having converged.
+    const INVALID_PENALTY: f64 = 1e12;
+
+    /// Nelder-Mead simplex search over a flat parameter vector. Invalid
+    /// parameter combinations are penalized rather than excluded, which keeps
+    /// the objective defined everywhere while still steering the simplex away
+    /// from them ("bounded" in the sense the calibration request asked for).
+    fn nelder_mead<F: Fn(&[f64]) -> f64>(objective: F, x0: Vec<f64>, max_iter: usize) -> (Vec<f64>, f64) {
+        let n = x0.len();
+        let (reflect, expand, contract, shrink) = (1.0, 2.0, 0.5, 0.5);
+
+        // Reflect/expand can push a valid-looking parameter set (per `is_valid`)
+        // into a branch-cut region where `phi` overflows to NaN/inf; treat that
+        // the same as an invalid point rather than letting it reach `partial_cmp`.
+        let eval = |x: &[f64]| -> f64 {
+            let v = objective(x);
+            if v.is_finite() {
+                v
+            } else {
+                INVALID_PENALTY
+            }
+        };
+
+        let mut simplex: Vec<Vec<f64>> = vec![x0.clone()];
+        for i in 0..n {
+            let mut x = x0.clone();
+            x[i] += if x[i].abs() > 1e-8 { x[i] * 0.1 } else { 0.1 };
+            simplex.push(x);
+        }
+        let mut values: Vec<f64> = simplex.iter().map(|x| eval(x)).collect();
+
+        // A +/-10% perturbation of an infeasible x0 is often still infeasible
+        // in the same way (e.g. a Feller-condition violation is rarely fixed
+        // by a small move in one parameter), leaving every vertex at
+        // `INVALID_PENALTY` with no gradient to climb down. Widen the
+        // perturbation geometrically until at least one vertex is feasible,
+        // so the simplex has a finite point to contract toward instead of
+        // shrinking back onto the infeasible x0 forever.
+        let mut scale = 0.2;
+        while values.iter().all(|&v| v >= INVALID_PENALTY) && scale <= 12.8 {
+            for i in 0..n {
+                let mut x = x0.clone();
+                x[i] += if x[i].abs() > 1e-8 { x[i] * scale } else { scale };
+                simplex[i + 1] = x;
+                values[i + 1] = eval(&simplex[i + 1]);
+            }
+            scale *= 2.0;
+        }
+
+        for _ in 0..max_iter {
+            let mut order: Vec<usize> = (0..=n).collect();
+            order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap());
+            simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+            values = order.iter().map(|&i| values[i]).collect();
+
+            // A flat `INVALID_PENALTY` plateau (no vertex has found a feasible
+            // point yet) must not read as convergence, or an infeasible `x0`
+            // makes `calibrate` return the unmodified initial guess.
+            if values[0] < INVALID_PENALTY && (values[n] - values[0]).abs() < 1e-12 {
+                break;
+            }
+
+            let centroid: Vec<f64> = (0..n)
+                .map(|k| simplex[..n].iter().map(|x| x[k]).sum::<f64>() / n as f64)
+                .collect();
+
+            let reflected: Vec<f64> = (0..n).map(|k| centroid[k] + reflect * (centroid[k] - simplex[n][k])).collect();
+            let f_reflected = eval(&reflected);
+
+            if f_reflected < values[0] {
+                let expanded: Vec<f64> = (0..n).map(|k| centroid[k] + expand * (reflected[k] - centroid[k])).collect();
+                let f_expanded = eval(&expanded);
+                if f_expanded < f_reflected {
+                    simplex[n] = expanded;
+                    values[n] = f_expanded;
+                } else {
+                    simplex[n] = reflected;
+                    values[n] = f_reflected;
+                }
+            } else if f_reflected < values[n - 1] {
+                simplex[n] = reflected;
+                values[n] = f_reflected;
+            } else {
+                let contracted: Vec<f64> = (0..n).map(|k| centroid[k] + contract * (simplex[n][k] - centroid[k])).collect();
+                let f_contracted = eval(&contracted);
+                if f_contracted < values[n] {
+                    simplex[n] = contracted;
+                    values[n] = f_contracted;
+                } else {
+                    let best = simplex[0].clone();
+                    for i in 1..=n {
+                        simplex[i] = best.iter().zip(simplex[i].iter()).map(|(b, x)| b + shrink * (x - b)).collect();
+                        values[i] = eval(&simplex[i]);
+                    }
+                }
+            }
+        }
+
+        let best = (0..=n).min_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap()).unwrap();
+        (simplex[best].clone(), values[best])
+    }
+
+    /// Fits a model's parameters to a quoted option chain by minimizing
+    /// weighted squared pricing error across all quotes via Nelder-Mead.
+    /// Returns the fitted model and its residual (sum of squared errors).
+    pub fn calibrate<M: CharacteristicFunction + ModelParams>(
+        quotes: &[Quote],
+        initial_guess: M,
+        s0: f64,
+        r: f64,
+        q: f64,
+    ) -> (M, f64) {
+        let groups = group_by_maturity(quotes);
+        let objective = |x: &[f64]| -> f64 {
+            let model = M::from_vector(x);
+            if !model.is_valid() {
+                return INVALID_PENALTY;
+            }
+            pricing_error(&model, s0, r, q, &groups)
+        };
+
+        let (x_opt, residual) = nelder_mead(objective, initial_guess.to_vector(), 500);
+        (M::from_vector(&x_opt), residual)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::fft_pricer::nearest_call_price;
+        use crate::models::VarianceGamma;
+
+        /// Synthetic noiseless quotes priced straight off `model`, so
+        /// `calibrate` has a known-recoverable target to fit back to.
+        fn synthetic_quotes<M: CharacteristicFunction>(
+            model: &M,
+            s0: f64,
+            r: f64,
+            q: f64,
+            maturities: &[f64],
+            strikes: &[f64],
+        ) -> Vec<Quote> {
+            let params = CarrMadanParams { alpha: 1.5, eta: 0.25, n: 10, beta: 5.0 };
+            let mut quotes = Vec::new();
+            for &t in maturities {
+                let grid = price_calls_grid(model, s0, r, q, t, params);
+                for &strike in strikes {
+                    let market_price = nearest_call_price(&grid, strike);
+                    quotes.push(Quote { strike, maturity: t, market_price, weight: 1.0 });
+                }
+            }
+            quotes
+        }
+
+        const S0: f64 = 100.0;
+        const R: f64 = 0.03;
+        const Q: f64 = 0.01;
+        const MATURITIES: [f64; 2] = [0.25, 0.75];
+        const STRIKES: [f64; 5] = [85.0, 95.0, 100.0, 105.0, 115.0];
+
+        #[test]
+        fn calibrate_heston_from_feasible_guess_fits_synthetic_quotes() {
+            let truth = Heston { kappa: 2.0, theta: 0.06, vol_of_vol: 0.3, rho: -0.6, v0: 0.05 };
+            let quotes = synthetic_quotes(&truth, S0, R, Q, &MATURITIES, &STRIKES);
+
+            let guess = Heston { kappa: 1.5, theta: 0.05, vol_of_vol: 0.35, rho: -0.5, v0: 0.04 };
+            let (_, residual) = calibrate(&quotes, guess, S0, R, Q);
+
+            assert!(residual < 1.0, "residual={residual} should recover close to noiseless synthetic quotes");
+        }
+
+        #[test]
+        fn calibrate_heston_from_borderline_infeasible_guess_still_fits() {
+            // Regression test: 2*kappa*theta = 0.16 < vol_of_vol^2 = 0.25, a
+            // Feller-condition violation, which used to make `calibrate`
+            // return this guess completely unchanged (residual stuck at the
+            // INVALID_PENALTY sentinel) instead of actually searching.
+            let truth = Heston { kappa: 2.0, theta: 0.06, vol_of_vol: 0.3, rho: -0.6, v0: 0.05 };
+            let quotes = synthetic_quotes(&truth, S0, R, Q, &MATURITIES, &STRIKES);
+
+            let guess = Heston { kappa: 1.0, theta: 0.08, vol_of_vol: 0.5, rho: -0.3, v0: 0.06 };
+            assert!(!guess.is_valid(), "guess should violate the Feller condition");
+
+            let (_, residual) = calibrate(&quotes, guess, S0, R, Q);
+
+            assert!(residual < 1.0, "residual={residual} should not be stuck at the infeasible-guess penalty");
+        }
+
+        #[test]
+        fn calibrate_variance_gamma_from_feasible_guess_fits_synthetic_quotes() {
+            let truth = VarianceGamma { sigma: 0.2, nu: 0.3, theta: -0.1 };
+            let quotes = synthetic_quotes(&truth, S0, R, Q, &MATURITIES, &STRIKES);
+
+            let guess = VarianceGamma { sigma: 0.25, nu: 0.25, theta: -0.05 };
+            let (fitted, residual) = calibrate(&quotes, guess, S0, R, Q);
+
+            assert!(residual < 1.0, "residual={residual} should recover close to noiseless synthetic quotes");
+            assert!((fitted.sigma - truth.sigma).abs() < 0.05, "sigma={}", fitted.sigma);
+            assert!((fitted.nu - truth.nu).abs() < 0.1, "nu={}", fitted.nu);
+            assert!((fitted.theta - truth.theta).abs() < 0.05, "theta={}", fitted.theta);
+        }
+    }
+}
+
+/// Optional integration with a live market-data source, gated behind the
+/// `market` feature so the core pricing/calibration path carries no network
+/// or JSON dependencies by default.
+#[cfg(feature = "market")]
+pub mod market {
+    use serde::Deserialize;
+    use std::fmt;
+
+    use crate::calibration::Quote;
+    use crate::fft_pricer::{price_put_cos, CosParams};
+    use crate::models::BlackScholes;
+
+    #[derive(Debug)]
+    pub enum MarketError {
+        Http(String),
+        Json(String),
+        MissingField(&'static str),
+    }
+
+    impl fmt::Display for MarketError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MarketError::Http(msg) => write!(f, "market data request failed: {msg}"),
+                MarketError::Json(msg) => write!(f, "market data response could not be parsed: {msg}"),
+                MarketError::MissingField(name) => write!(f, "market data response missing field `{name}`"),
+            }
+        }
+    }
+
+    impl std::error::Error for MarketError {}
+
+    // Yahoo-Finance-style option-chain response shape (only the fields we need).
+    #[derive(Deserialize)]
+    struct ChainResponse {
+        #[serde(rename = "optionChain")]
+        option_chain: OptionChain,
+    }
+
+    #[derive(Deserialize)]
+    struct OptionChain {
+        result: Vec<ChainResult>,
+    }
+
+    #[derive(Deserialize)]
+    struct ChainResult {
+        quote: SpotQuote,
+        #[serde(rename = "options")]
+        expirations: Vec<ExpirationChain>,
+    }
+
+    #[derive(Deserialize)]
+    struct SpotQuote {
+        #[serde(rename = "regularMarketPrice")]
+        regular_market_price: f64,
+    }
+
+    #[derive(Deserialize)]
+    struct ExpirationChain {
+        #[serde(rename = "expirationDate")]
+        expiration_date: i64, // unix seconds
+        puts: Vec<Contract>,
+    }
+
+    #[derive(Deserialize)]
+    struct Contract {
+        strike: f64,
+        bid: f64,
+        ask: f64,
+    }
+
+    /// Spot price plus a calibration-ready set of quotes pulled from a
+    /// Yahoo-Finance-style option-chain endpoint.
+    pub struct MarketSnapshot {
+        pub spot: f64,
+        pub quotes: Vec<Quote>,
+    }
+
+    const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
+
+    /// Fetches an underlying's spot price and listed put chain from `endpoint` (a
+    /// Yahoo-Finance-style `.../v7/finance/options/{symbol}` URL) and turns mid
+    /// quotes into [`Quote`]s ready for [`crate::calibration::calibrate`].
+    /// Put mids are converted to call prices via put-call parity, since
+    /// [`Quote::market_price`] is a call price everywhere it's consumed.
+    /// `r` and `q` are the discount/dividend rates used for that conversion.
+    /// `as_of_unix` is the snapshot time, used to convert expiration dates into
+    /// year fractions; expirations already in the past are skipped.
+    pub fn fetch_option_chain(endpoint: &str, as_of_unix: i64, r: f64, q: f64) -> Result<MarketSnapshot, MarketError> {
+        let body = ureq::get(endpoint)
+            .call()
+            .map_err(|e| MarketError::Http(e.to_string()))?
+            .into_string()
+            .map_err(|e| MarketError::Http(e.to_string()))?;
+
+        let parsed: ChainResponse = serde_json::from_str(&body).map_err(|e| MarketError::Json(e.to_string()))?;
+        let result = parsed.option_chain.result.into_iter().next().ok_or(MarketError::MissingField("result"))?;
+        let spot = result.quote.regular_market_price;
+
+        let mut quotes = Vec::new();
+        for expiration in result.expirations {
+            let maturity = (expiration.expiration_date - as_of_unix) as f64 / SECONDS_PER_YEAR;
+            if maturity <= 0.0 {
+                continue;
+            }
+            for contract in &expiration.puts {
+                if let Some(put_mid) = mid_price(contract) {
+                    // Call from parity: C = P + S0 e^{-qT} - K e^{-rT}
+                    let call_price = put_mid + spot * (-q * maturity).exp() - contract.strike * (-r * maturity).exp();
+                    quotes.push(Quote { strike: contract.strike, maturity, market_price: call_price, weight: 1.0 });
+                }
+            }
+        }
+
+        Ok(MarketSnapshot { spot, quotes })
+    }
+
+    fn mid_price(contract: &Contract) -> Option<f64> {
+        if contract.bid > 0.0 && contract.ask > contract.bid {
+            Some(0.5 * (contract.bid + contract.ask))
+        } else {
+            None
+        }
+    }
+
+    /// Implied Black-Scholes volatility for a single put quote, bracketed by
+    /// bisection on [`price_put_cos`] over `sigma in [1e-4, 5.0]`. Unlike
+    /// [`price_put_at_strike`](crate::fft_pricer::price_put_at_strike), the COS
+    /// pricer has no nearest-node grid error to bias the recovered vol, and
+    /// needs no damping parameter tuned for numerical stability across the
+    /// bracket.
+    /// Returns `None` if the market price falls outside what that bracket can
+    /// produce (arbitrage violation, or a dividend/rate input far off).
+    pub fn implied_vol(s0: f64, r: f64, q: f64, t: f64, k_strike: f64, market_price: f64) -> Option<f64> {
+        let params = CosParams::default();
+        let price_at = |sigma: f64| {
+            let model = BlackScholes { sigma };
+            price_put_cos(&model, s0, r, q, t, params, k_strike)
+        };
+
+        let (mut lo, mut hi) = (1e-4_f64, 5.0_f64);
+        let mut f_lo = price_at(lo) - market_price;
+        let f_hi = price_at(hi) - market_price;
+        if f_lo.signum() == f_hi.signum() {
+            return None;
+        }
+
+        for _ in 0..100 {
+            let mid = 0.5 * (lo + hi);
+            let f_mid = price_at(mid) - market_price;
+            if f_mid.abs() < 1e-8 || (hi - lo) < 1e-8 {
+                return Some(mid);
+            }
+            if f_mid.signum() == f_lo.signum() {
+                lo = mid;
+                f_lo = f_mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        Some(0.5 * (lo + hi))
+    }
+
+    /// One point on an implied-vol surface: a maturity/strike pair and its
+    /// Black-Scholes implied vol (`None` where [`implied_vol`] could not
+    /// bracket the market price).
+    pub struct VolPoint {
+        pub strike: f64,
+        pub maturity: f64,
+        pub vol: Option<f64>,
+    }
+
+    /// Builds an implied-vol surface from a [`MarketSnapshot`] by inverting
+    /// every quote independently via [`implied_vol`]. `quote.market_price` is
+    /// a call price (see [`Quote`]); it's converted back to a put price via
+    /// parity since `implied_vol` inverts [`price_put_cos`].
+    pub fn vol_surface(snapshot: &MarketSnapshot, r: f64, q: f64) -> Vec<VolPoint> {
+        snapshot
+            .quotes
+            .iter()
+            .map(|quote| {
+                // Put from parity: P = C - S0 e^{-qT} + K e^{-rT}
+                let put_price = quote.market_price - snapshot.spot * (-q * quote.maturity).exp()
+                    + quote.strike * (-r * quote.maturity).exp();
+                VolPoint {
+                    strike: quote.strike,
+                    maturity: quote.maturity,
+                    vol: implied_vol(snapshot.spot, r, q, quote.maturity, quote.strike, put_price),
+                }
+            })
+            .collect()
     }
 } 
\ No newline at end of file